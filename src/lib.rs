@@ -34,9 +34,10 @@
 //! buf.write(&[0; 1024]).unwrap();
 //! ```
 
+use std::cmp;
 use std::fmt;
 use std::io::prelude::*;
-use std::io::{self, BufReader, BufWriter};
+use std::io::{self, BufReader, BufWriter, IoSlice, IoSliceMut, SeekFrom};
 
 const DEFAULT_BUF_SIZE: usize = 64 * 1024;
 
@@ -49,8 +50,10 @@ const DEFAULT_BUF_SIZE: usize = 64 * 1024;
 ///
 /// The output buffer will be written out when this stream is dropped.
 #[derive(Debug)]
-pub struct BufStream<S: Write> {
-    inner: BufReader<InternalBufWriter<S>>
+pub struct BufStream<S: ?Sized + Write> {
+    // `inner` holds the (possibly unsized) stream, so it must come last.
+    line_buffered: bool,
+    inner: BufReader<InternalBufWriter<S>>,
 }
 
 /// An error returned by `into_inner` which combines an error that
@@ -59,27 +62,33 @@ pub struct BufStream<S: Write> {
 #[derive(Debug)]
 pub struct IntoInnerError<W>(W, io::Error);
 
-struct InternalBufWriter<W: Write>(Option<BufWriter<W>>);
+struct InternalBufWriter<W: ?Sized + Write>(BufWriter<W>);
 
-impl<W: Write> InternalBufWriter<W> {
+impl<W: ?Sized + Write> InternalBufWriter<W> {
     fn get_ref(&self) -> &BufWriter<W> {
         let InternalBufWriter(ref w) = *self;
-        w.as_ref().unwrap()
+        w
     }
 
     fn get_mut(&mut self) -> &mut BufWriter<W> {
         let InternalBufWriter(ref mut w) = *self;
-        w.as_mut().unwrap()
+        w
     }
 }
 
-impl<W: Read + Write> Read for InternalBufWriter<W> {
+impl<W: ?Sized + Read + Write> Read for InternalBufWriter<W> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.get_mut().get_mut().read(buf)
     }
 }
 
-impl<W: Write + fmt::Debug> fmt::Debug for InternalBufWriter<W> {
+impl<W: ?Sized + Read + Write + Seek> Seek for InternalBufWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.get_mut().seek(pos)
+    }
+}
+
+impl<W: ?Sized + Write + fmt::Debug> fmt::Debug for InternalBufWriter<W> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.get_ref().fmt(f)
     }
@@ -91,17 +100,80 @@ impl<S: Read + Write> BufStream<S> {
     pub fn with_capacities(reader_cap: usize, writer_cap: usize, inner: S)
                            -> BufStream<S> {
         let writer = BufWriter::with_capacity(writer_cap, inner);
-        let internal_writer = InternalBufWriter(Some(writer));
+        let internal_writer = InternalBufWriter(writer);
         let reader = BufReader::with_capacity(reader_cap, internal_writer);
-        BufStream { inner: reader }
+        BufStream { inner: reader, line_buffered: false }
     }
 
     /// Creates a new buffered stream with the default reader/writer buffer
     /// capacities.
+    ///
+    /// Like `with_capacities`, this picks the dual-buffer mode in which the
+    /// reading and writing halves are buffered independently. See `unified`
+    /// for the single-buffer alternative.
     pub fn new(inner: S) -> BufStream<S> {
         BufStream::with_capacities(DEFAULT_BUF_SIZE, DEFAULT_BUF_SIZE, inner)
     }
 
+    /// Creates a buffered stream that uses a *single* buffer shared between
+    /// reading and writing.
+    ///
+    /// Unlike `new`/`with_capacities`, which give each half its own buffer, the
+    /// returned `UnifiedBufStream` keeps the buffer in at most one mode at a
+    /// time and flushes (or invalidates) the other mode when the direction
+    /// switches. This matches the behavior of C#'s `BufferedStream` and avoids
+    /// the hazard of a read observing stale data that was logically overwritten
+    /// by buffered-but-unflushed writes on a seekable stream, which is why the
+    /// dual-buffer mode has surprising semantics for `BufStream<File>`. Prefer
+    /// the dual-buffer constructors for full-duplex sockets.
+    pub fn unified(inner: S) -> UnifiedBufStream<S> {
+        UnifiedBufStream::new(inner)
+    }
+
+    /// Creates a new buffered stream with the write half in line-buffered mode.
+    ///
+    /// See `set_line_buffered` for what line buffering does. This is a
+    /// convenience for `BufStream::new` followed by `set_line_buffered(true)`.
+    pub fn with_line_buffering(inner: S) -> BufStream<S> {
+        let mut stream = BufStream::new(inner);
+        stream.line_buffered = true;
+        stream
+    }
+
+    /// Unwraps this `BufStream`, returning the underlying stream.
+    ///
+    /// The internal write buffer is written out before returning the stream.
+    /// Any leftover data in the read buffer is lost.
+    pub fn into_inner(self) -> Result<S, IntoInnerError<BufStream<S>>> {
+        let reader_cap = self.inner.capacity();
+        let line_buffered = self.line_buffered;
+        // Dropping the `BufReader` discards any leftover read buffer.
+        let InternalBufWriter(writer) = self.inner.into_inner();
+        match writer.into_inner() {
+            Ok(s) => Ok(s),
+            Err(err) => {
+                let e = io::Error::new(err.error().kind(), err.error().to_string());
+                let reader = BufReader::with_capacity(
+                    reader_cap, InternalBufWriter(err.into_inner()));
+                Err(IntoInnerError(BufStream { inner: reader, line_buffered }, e))
+            }
+        }
+    }
+}
+
+impl<S: ?Sized + Read + Write> BufStream<S> {
+    /// Enables or disables line buffering on the write half.
+    ///
+    /// With line buffering enabled, each `write` flushes everything up to and
+    /// including the last newline in the incoming bytes immediately, keeping
+    /// only the trailing partial line buffered. This is convenient for
+    /// line-oriented protocols (SMTP, IRC, Redis inline commands) where a full
+    /// line should reach the socket without the caller remembering to flush.
+    /// The default is full block buffering, which is left unchanged here.
+    pub fn set_line_buffered(&mut self, line_buffered: bool) {
+        self.line_buffered = line_buffered;
+    }
+
     /// Gets a reference to the underlying stream.
     pub fn get_ref(&self) -> &S {
         self.inner.get_ref().get_ref().get_ref()
@@ -117,53 +189,360 @@ impl<S: Read + Write> BufStream<S> {
         self.inner.get_mut().get_mut().get_mut()
     }
 
-    /// Unwraps this `BufStream`, returning the underlying stream.
+    /// Returns the bytes currently buffered in the read half but not yet
+    /// consumed, without advancing the reader.
     ///
-    /// The internal write buffer is written out before returning the stream.
-    /// Any leftover data in the read buffer is lost.
-    pub fn into_inner(mut self) -> Result<S, IntoInnerError<BufStream<S>>> {
-        let e = {
-            let InternalBufWriter(ref mut w) = *self.inner.get_mut();
-            let (e, w2) = match w.take().unwrap().into_inner() {
-                Ok(s) => return Ok(s),
-                Err(err) => {
-                    (io::Error::new(err.error().kind(), err.error().to_string()),
-                     err.into_inner())
-                }
-            };
-            *w = Some(w2);
-            e
-        };
-        Err(IntoInnerError(self, e))
+    /// Useful for a protocol parser that wants to check whether a full frame is
+    /// already available before issuing another `fill_buf`.
+    pub fn buffer(&self) -> &[u8] {
+        self.inner.buffer()
+    }
+
+    /// Returns the bytes currently queued in the write half awaiting a flush.
+    pub fn write_buffer(&self) -> &[u8] {
+        self.inner.get_ref().get_ref().buffer()
+    }
+
+    /// Returns the capacity of the read buffer.
+    pub fn reader_capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Returns the capacity of the write buffer.
+    pub fn writer_capacity(&self) -> usize {
+        self.inner.get_ref().get_ref().capacity()
+    }
+
+    /// Returns the number of unconsumed bytes buffered in the read half.
+    pub fn reader_buffered(&self) -> usize {
+        self.buffer().len()
+    }
+
+    /// Returns the number of unflushed bytes buffered in the write half.
+    pub fn writer_buffered(&self) -> usize {
+        self.write_buffer().len()
+    }
+
+    /// Hints whether assembling `IoSlice`s for `write_vectored` is worthwhile.
+    ///
+    /// There is no stable way to ask the wrapped stream whether its own
+    /// vectored writes are efficient, so this always returns `true`. With line
+    /// buffering off, `write_vectored` gathers all slices into the buffer or,
+    /// for gather-writes at least as large as the buffer, forwards them
+    /// straight to the underlying stream's `write_vectored`. With line
+    /// buffering on, each `write_vectored` call writes only the first
+    /// non-empty slice.
+    pub fn underlying_is_write_vectored(&self) -> bool {
+        true
     }
 }
 
-impl<S: Read + Write> BufRead for BufStream<S> {
+impl<S: ?Sized + Read + Write> BufRead for BufStream<S> {
     fn fill_buf(&mut self) -> io::Result<&[u8]> { self.inner.fill_buf() }
     fn consume(&mut self, amt: usize) { self.inner.consume(amt) }
 }
 
-impl<S: Read + Write> Read for BufStream<S> {
+impl<S: ?Sized + Read + Write> Read for BufStream<S> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
 }
 
-impl<S: Read + Write> Write for BufStream<S> {
+impl<S: ?Sized + Read + Write> Write for BufStream<S> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.get_mut().0.as_mut().unwrap().get_mut().write(buf)
+        if !self.line_buffered {
+            return self.inner.get_mut().get_mut().write(buf);
+        }
+        // Line-buffered: flush through the last newline so the completed
+        // line(s) hit the stream now, and buffer only the trailing fragment.
+        match buf.iter().rposition(|&b| b == b'\n') {
+            None => self.inner.get_mut().get_mut().write(buf),
+            Some(i) => {
+                let w = self.inner.get_mut().get_mut();
+                w.write_all(&buf[..=i])?;
+                w.flush()?;
+                w.write_all(&buf[i + 1..])?;
+                Ok(buf.len())
+            }
+        }
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if self.line_buffered {
+            // Line buffering scans a single contiguous range for its newline,
+            // so fall back to the per-slice `write` path (writing the first
+            // non-empty slice, as the default vectored write does) rather than
+            // gathering everything into the buffer and skipping the flush.
+            for buf in bufs {
+                if !buf.is_empty() {
+                    return self.write(buf);
+                }
+            }
+            return Ok(0);
+        }
+        let w = self.inner.get_mut().get_mut();
+        let total: usize = bufs.iter().map(|b| b.len()).sum();
+        // If the incoming gather-write won't fit alongside what is already
+        // buffered, flush to make room first.
+        if total > w.capacity() - w.buffer().len() {
+            w.flush()?;
+        }
+        // For payloads that would fill the buffer on their own there is no
+        // point copying them in just to copy them straight back out; hand the
+        // slices directly to the underlying stream's vectored write.
+        if total >= w.capacity() {
+            w.get_mut().write_vectored(bufs)
+        } else {
+            w.write_vectored(bufs)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.get_mut().get_mut().flush()
+    }
+}
+
+impl<S: ?Sized + Read + Write + Seek> Seek for BufStream<S> {
+    /// Seeks to an offset, in bytes, in the underlying stream.
+    ///
+    /// Because both halves are buffered this is more than a plain delegation.
+    /// The pending write buffer is flushed to the underlying stream first, so
+    /// the kernel offset reflects everything that has been written. The read
+    /// buffer, however, has typically read *ahead* of that offset, so the true
+    /// logical position is the underlying offset minus the number of unconsumed
+    /// bytes still sitting in the read buffer; `SeekFrom::Current(n)` is
+    /// translated against that corrected position rather than the read-ahead
+    /// one. Once the seek lands, the read buffer no longer corresponds to the
+    /// new position and is discarded.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<S: ?Sized + Read + Write + Seek> BufStream<S> {
+    /// Seeks relative to the current position.
+    ///
+    /// If the new position lands within the bytes currently held in the read
+    /// buffer this simply advances the in-memory cursor and performs no seek on
+    /// the underlying stream. Otherwise it behaves like
+    /// `seek(SeekFrom::Current(offset))`, flushing pending writes and
+    /// discarding the read buffer.
+    pub fn seek_relative(&mut self, offset: i64) -> io::Result<()> {
+        self.inner.seek_relative(offset)
+    }
+}
+
+/// A buffered stream that reuses a *single* buffer for both directions.
+///
+/// At any moment the buffer is in at most one mode: it either holds bytes read
+/// ahead from the stream or bytes queued for writing, never both. Switching
+/// from writing to reading flushes the pending output first; switching from
+/// reading to writing invalidates the unconsumed read-ahead. This mirrors
+/// C#'s `BufferedStream` and sidesteps the full-duplex correctness hazard of
+/// the independently buffered `BufStream`, at the cost of not being able to
+/// keep read and write buffers live simultaneously.
+///
+/// Like `BufStream`, the output buffer is written out when this stream is
+/// dropped. Construct one with [`BufStream::unified`].
+#[derive(Debug)]
+pub struct UnifiedBufStream<S: Read + Write> {
+    inner: S,
+    buf: Box<[u8]>,
+    // Index of the next buffered byte to hand out while reading.
+    pos: usize,
+    // While reading, the number of valid bytes in `buf`; while writing, the
+    // number of buffered bytes awaiting a flush.
+    end: usize,
+    mode: Mode,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    /// The buffer holds nothing.
+    Empty,
+    /// `buf[pos..end]` has been read ahead from the underlying stream.
+    Reading,
+    /// `buf[..end]` is buffered output awaiting a flush.
+    Writing,
+}
+
+impl<S: Read + Write> UnifiedBufStream<S> {
+    /// Creates a new single-buffer stream with the default buffer capacity.
+    pub fn new(inner: S) -> UnifiedBufStream<S> {
+        UnifiedBufStream::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new single-buffer stream with an explicit buffer capacity.
+    pub fn with_capacity(cap: usize, inner: S) -> UnifiedBufStream<S> {
+        UnifiedBufStream {
+            inner,
+            buf: vec![0; cap].into_boxed_slice(),
+            pos: 0,
+            end: 0,
+            mode: Mode::Empty,
+        }
+    }
+
+    /// Gets a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying stream.
+    ///
+    /// # Warning
+    ///
+    /// It is inadvisable to read directly from or write directly to the
+    /// underlying stream.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Flushes any buffered output and leaves the buffer empty, so the next
+    /// operation may pick either mode. Read-ahead is discarded, not written.
+    fn flush_buf(&mut self) -> io::Result<()> {
+        if self.mode == Mode::Writing && self.end > 0 {
+            self.inner.write_all(&self.buf[..self.end])?;
+        }
+        self.pos = 0;
+        self.end = 0;
+        self.mode = Mode::Empty;
+        Ok(())
     }
+}
+
+impl<S: Read + Write> BufRead for UnifiedBufStream<S> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.mode == Mode::Writing {
+            self.flush_buf()?;
+        }
+        if self.pos >= self.end {
+            self.end = self.inner.read(&mut self.buf)?;
+            self.pos = 0;
+            self.mode = if self.end == 0 { Mode::Empty } else { Mode::Reading };
+        }
+        Ok(&self.buf[self.pos..self.end])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = cmp::min(self.pos + amt, self.end);
+        if self.pos >= self.end {
+            self.pos = 0;
+            self.end = 0;
+            if self.mode == Mode::Reading {
+                self.mode = Mode::Empty;
+            }
+        }
+    }
+}
+
+impl<S: Read + Write> Read for UnifiedBufStream<S> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        // With an empty read buffer and a request at least as large as the
+        // buffer, read straight into the caller's slice to skip a memcpy.
+        if self.pos >= self.end && out.len() >= self.buf.len() {
+            self.flush_buf()?;
+            return self.inner.read(out);
+        }
+        let nread = {
+            let available = self.fill_buf()?;
+            let n = cmp::min(available.len(), out.len());
+            out[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(nread);
+        Ok(nread)
+    }
+}
+
+impl<S: Read + Write> Write for UnifiedBufStream<S> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        // Switching from reading to writing invalidates the read-ahead.
+        if self.mode == Mode::Reading {
+            self.pos = 0;
+            self.end = 0;
+            self.mode = Mode::Empty;
+        }
+        // Flush first if the data won't fit alongside what is already buffered.
+        if self.end > 0 && self.end + data.len() > self.buf.len() {
+            self.inner.write_all(&self.buf[..self.end])?;
+            self.end = 0;
+            self.mode = Mode::Empty;
+        }
+        // Payloads at least as large as the whole buffer go straight through.
+        if data.len() >= self.buf.len() {
+            return self.inner.write(data);
+        }
+        let n = data.len();
+        self.buf[self.end..self.end + n].copy_from_slice(data);
+        self.end += n;
+        self.mode = Mode::Writing;
+        Ok(n)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.get_mut().0.as_mut().unwrap().get_mut().flush()
+        self.flush_buf()?;
+        self.inner.flush()
+    }
+}
+
+impl<S: Read + Write + Seek> Seek for UnifiedBufStream<S> {
+    /// Seeks to an offset in the underlying stream, flushing pending writes and
+    /// correcting for any read-ahead before repositioning. See
+    /// [`BufStream::seek`] for the accounting rationale.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if self.mode == Mode::Writing {
+            self.flush_buf()?;
+        }
+        let result = if let SeekFrom::Current(n) = pos {
+            let remainder = (self.end - self.pos) as i64;
+            self.inner.seek(SeekFrom::Current(n - remainder))?
+        } else {
+            self.inner.seek(pos)?
+        };
+        self.pos = 0;
+        self.end = 0;
+        self.mode = Mode::Empty;
+        Ok(result)
+    }
+}
+
+impl<S: Read + Write> Drop for UnifiedBufStream<S> {
+    fn drop(&mut self) {
+        if self.mode == Mode::Writing && self.end > 0 {
+            let _ = self.inner.write_all(&self.buf[..self.end]);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::prelude::*;
-    use std::io;
+    use std::io::{self, Cursor, IoSlice, IoSliceMut, SeekFrom};
 
     use super::BufStream;
+
+    // A stream that records everything actually written to it, so tests can
+    // observe what reached the underlying stream versus what stayed buffered.
+    #[derive(Default)]
+    struct Recorder {
+        written: Vec<u8>,
+    }
+
+    impl Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    impl Read for Recorder {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> { Ok(0) }
+    }
     // This is just here to make sure that we don't infinite loop in the
     // newtype struct autoderef weirdness
     #[test]
@@ -184,4 +563,112 @@ mod tests {
         stream.write(&[0; 10]).unwrap();
         stream.flush().unwrap();
     }
+
+    #[test]
+    fn test_seek_reports_logical_position() {
+        // After reading 4 bytes the reader has read ahead to the end of the
+        // stream, but the logical position is 4, not the kernel offset.
+        let mut stream = BufStream::new(Cursor::new((0u8..10).collect::<Vec<_>>()));
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0, 1, 2, 3]);
+        assert_eq!(stream.seek(SeekFrom::Current(0)).unwrap(), 4);
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut one = [0u8; 1];
+        stream.read_exact(&mut one).unwrap();
+        assert_eq!(one, [2]);
+    }
+
+    #[test]
+    fn test_seek_relative_stays_in_buffer() {
+        let mut stream = BufStream::new(Cursor::new((0u8..10).collect::<Vec<_>>()));
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        stream.seek_relative(2).unwrap();
+        let mut one = [0u8; 1];
+        stream.read_exact(&mut one).unwrap();
+        assert_eq!(one, [6]);
+    }
+
+    #[test]
+    fn test_line_buffering_flushes_through_last_newline() {
+        let mut stream = BufStream::with_line_buffering(Recorder::default());
+        stream.write_all(b"hello\nworld").unwrap();
+        assert_eq!(stream.get_ref().written, b"hello\n");
+        assert_eq!(stream.write_buffer(), b"world");
+    }
+
+    #[test]
+    fn test_line_buffering_applies_to_write_vectored() {
+        let mut stream = BufStream::with_line_buffering(Recorder::default());
+        let n = stream.write_vectored(&[IoSlice::new(b"hi\nthere")]).unwrap();
+        assert_eq!(n, 8);
+        assert_eq!(stream.get_ref().written, b"hi\n");
+        assert_eq!(stream.write_buffer(), b"there");
+    }
+
+    #[test]
+    fn test_write_vectored_gathers_all_slices() {
+        let mut stream = BufStream::new(Cursor::new(Vec::new()));
+        let n = stream
+            .write_vectored(&[IoSlice::new(b"ab"), IoSlice::new(b"cd")])
+            .unwrap();
+        assert_eq!(n, 4);
+        stream.flush().unwrap();
+        assert_eq!(stream.get_ref().get_ref().as_slice(), b"abcd");
+    }
+
+    #[test]
+    fn test_read_vectored_fills_from_buffer() {
+        let mut stream = BufStream::new(Cursor::new(vec![9, 8, 7]));
+        let (mut a, mut b) = ([0u8; 2], [0u8; 2]);
+        let mut slices = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+        assert_eq!(stream.read_vectored(&mut slices).unwrap(), 3);
+        assert_eq!(a, [9, 8]);
+        assert_eq!(b[0], 7);
+    }
+
+    #[test]
+    fn test_read_buffer_accessors() {
+        let mut stream = BufStream::new(Cursor::new(vec![1, 2, 3, 4]));
+        assert!(stream.reader_capacity() > 0);
+        let mut buf = [0u8; 2];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(stream.buffer(), &[3, 4]);
+        assert_eq!(stream.reader_buffered(), 2);
+    }
+
+    #[test]
+    fn test_write_buffer_accessors() {
+        let mut stream = BufStream::new(Cursor::new(Vec::new()));
+        assert!(stream.writer_capacity() > 0);
+        stream.write_all(b"xy").unwrap();
+        assert_eq!(stream.write_buffer(), b"xy");
+        assert_eq!(stream.writer_buffered(), 2);
+    }
+
+    #[test]
+    fn test_unsized_inner_stream() {
+        // `BufStream` must be usable behind a trait object now that the inner
+        // stream is `?Sized`.
+        trait ReadWrite: Read + Write {}
+        impl<T: Read + Write> ReadWrite for T {}
+
+        let concrete = BufStream::new(Cursor::new(Vec::new()));
+        let mut boxed: Box<BufStream<dyn ReadWrite>> = Box::new(concrete);
+        boxed.write_all(b"hi").unwrap();
+        assert_eq!(boxed.write_buffer(), b"hi");
+    }
+
+    #[test]
+    fn test_unified_direction_switch() {
+        // Buffered writes must be flushed before a seek, and the data must be
+        // readable back afterwards through the same single buffer.
+        let mut stream = BufStream::unified(Cursor::new(Vec::new()));
+        stream.write_all(b"abc").unwrap();
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        let mut out = Vec::new();
+        stream.read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"abc");
+    }
 }